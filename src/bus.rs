@@ -0,0 +1,45 @@
+/// Abstraction over everything a `CPU` can read from and write to.
+///
+/// Splitting memory access behind a trait means the CPU doesn't need to know
+/// whether an address lands in RAM, a PPU/APU register, or cartridge space -
+/// it just reads and writes bytes. A `Bus` implementation is free to trap
+/// specific address ranges and redirect them elsewhere.
+pub trait Bus {
+    fn read(&self, addr: u16) -> u8;
+    fn write(&mut self, addr: u16, data: u8);
+
+    fn read_u16(&self, pos: u16) -> u16 {
+        let lo = self.read(pos) as u16;
+        let hi = self.read(pos.wrapping_add(1)) as u16;
+        (hi << 8) | lo
+    }
+
+    fn write_u16(&mut self, addr: u16, data: u16) {
+        let lo = (data & 0xFF) as u8;
+        let hi = (data >> 8) as u8;
+        self.write(addr, lo);
+        self.write(addr.wrapping_add(1), hi);
+    }
+}
+
+/// Flat 64KB RAM with no memory-mapped devices. This is the default `Bus`
+/// used when nothing fancier (PPU/APU register trapping) is needed.
+pub struct Memory {
+    space: [u8; 0x10000],
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Memory { space: [0; 0x10000] }
+    }
+}
+
+impl Bus for Memory {
+    fn read(&self, addr: u16) -> u8 {
+        self.space[addr as usize]
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        self.space[addr as usize] = data;
+    }
+}
@@ -1,3 +1,4 @@
+use crate::bus::Bus;
 use crate::opcodes;
 
 bitflags! {
@@ -25,9 +26,20 @@ pub enum AddressingMode {
     Absolute_Y,
     Indirect_X,
     Indirect_Y,
+    Indirect,
+    IndirectZeroPage,
+    Relative,
     NoneAddressing,
 }
 
+/// Which instruction set `CPU` decodes: the original NMOS 6502, or the
+/// 65C02 (CMOS) revision with its extra opcodes and cleaned-up BRK behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cpu6502Variant {
+    Nmos,
+    Cmos,
+}
+
 pub struct OpCode {
     pub instruction: u8,
     pub label: String,
@@ -36,57 +48,72 @@ pub struct OpCode {
     pub mode: AddressingMode
 }
 
+const STACK: u16 = 0x0100;
+const STACK_RESET: u8 = 0xFD;
+
 // Define CPU and its registers
-pub struct CPU {
+pub struct CPU<M: Bus> {
     pub register_a: u8,
     pub register_x: u8,
     pub register_y: u8,
     pub status: StatusFlags,
     pub program_counter: u16,
-    memory: [u8; 0xFFFF]
+    pub stack_pointer: u8,
+    pub variant: Cpu6502Variant,
+    memory: M
 }
 
-impl CPU {
-    pub fn new() -> Self {
+impl<M: Bus> CPU<M> {
+    pub fn new(memory: M) -> Self {
         CPU {
             register_a: 0,
             register_x: 0,
             register_y: 0,
             status: StatusFlags::from_bits_truncate(0b100100),
             program_counter: 0,
-            memory: [0; 0xFFFF]
+            stack_pointer: STACK_RESET,
+            variant: Cpu6502Variant::Nmos,
+            memory
         }
     }
 
+    fn stack_push(&mut self, data: u8) {
+        self.mem_write(STACK + self.stack_pointer as u16, data);
+        self.stack_pointer = self.stack_pointer.wrapping_sub(1);
+    }
+
+    fn stack_pop(&mut self) -> u8 {
+        self.stack_pointer = self.stack_pointer.wrapping_add(1);
+        self.mem_read(STACK + self.stack_pointer as u16)
+    }
+
+    fn stack_push_u16(&mut self, data: u16) {
+        let hi = (data >> 8) as u8;
+        let lo = (data & 0xFF) as u8;
+        self.stack_push(hi);
+        self.stack_push(lo);
+    }
+
+    fn stack_pop_u16(&mut self) -> u16 {
+        let lo = self.stack_pop() as u16;
+        let hi = self.stack_pop() as u16;
+        (hi << 8) | lo
+    }
+
     pub fn mem_read(&self, addr: u16) -> u8 {
-        self.memory[addr as usize]
-    } 
+        self.memory.read(addr)
+    }
 
     pub fn mem_read_u16(&self, pos: u16) -> u16 {
-        // read byte at lower address
-        let lo = self.mem_read(pos) as u16;
-        // read by at higher address
-        let hi = self.mem_read(pos + 1) as u16;
-        // combines the hi and lo byte with little endian ordering.
-        // shifts the hi byte 8 bits to the left of the lo byte, uses the OR operator to combine
-        (hi << 8) | (lo as u16)
+        self.memory.read_u16(pos)
     }
 
     pub fn mem_write(&mut self, addr: u16, data: u8) {
-        self.memory[addr as usize] = data;
+        self.memory.write(addr, data);
     }
 
     pub fn mem_write_u16(&mut self, addr: u16, data: u16) {
-        // From data, shift the most significant 8 bits into the position of the least significant
-        // then truncate, preserving least significant bits
-        let hi = (data >> 8) as u8;
-        // preserve only the least significant bits by comparing data (16bits) to 8 set bits and
-        // then truncate, preserving least significant bits again
-        let lo = (data & 0xFF) as u8;
-
-        self.mem_write(addr, lo);
-        self.mem_write(addr + 1, hi);
-        
+        self.memory.write_u16(addr, data);
     }
 
     pub fn load_and_run(&mut self, program: Vec<u8>) {
@@ -99,70 +126,179 @@ impl CPU {
         self.register_a = 0;
         self.register_x = 0;
         self.status = StatusFlags::from_bits_retain(0b100100);
+        self.stack_pointer = STACK_RESET;
 
         // Reset program to special program start point defined by program ROMs
         self.program_counter = self.mem_read_u16(0xFFFC);
     }
 
     pub fn load(&mut self, program: Vec<u8>) {
-        self.memory[0x8000 .. (0x8000 + program.len())].copy_from_slice(&program[..]);
+        for (offset, byte) in program.iter().enumerate() {
+            self.mem_write(0x8000 + offset as u16, *byte);
+        }
         self.mem_write_u16(0xFFFC, 0x8000);
     }
 
     pub fn run(&mut self) {
-        let ref opcodes = *opcodes::OPCODES_MAP;
+        self.run_with_callback(|_| {});
+    }
 
+    pub fn run_with_callback<F: FnMut(&mut CPU<M>)>(&mut self, mut callback: F) {
         loop {
-            let code = self.mem_read(self.program_counter);
-            self.program_counter += 1;
-            let program_counter_state = self.program_counter;
-            
-            let opcode = opcodes.get(&code).expect(&format!("OpCode: {:x} is not recognized", code));
-
-            match code {
-                0xa9 | 0xa5 | 0xb5 | 0xad | 0xbd | 0xb9 | 0xa1 | 0xb1 => {
-                    self.lda(&opcode.mode);
-                }
+            callback(self);
 
-                /* STA */
-                0x85 | 0x95 | 0x8d | 0x9d | 0x99 | 0x81 | 0x91 => {
-                    self.sta(&opcode.mode);
-                }
+            if !self.step() {
+                break;
+            }
+        }
+    }
+
+    /// Executes exactly one instruction. Returns `false` once a `BRK` is hit,
+    /// so callers (`run`/`run_with_callback`) know to stop.
+    pub fn step(&mut self) -> bool {
+        let ref opcodes = *opcodes::OPCODES_MAP;
+
+        let code = self.mem_read(self.program_counter);
+        self.program_counter += 1;
+        let program_counter_state = self.program_counter;
+
+        let opcode = opcodes.get(&code).expect(&format!("OpCode: {:x} is not recognized", code));
+
+        match code {
+            0xa9 | 0xa5 | 0xb5 | 0xad | 0xbd | 0xb9 | 0xa1 | 0xb1 => {
+                self.lda(&opcode.mode);
+            }
+
+            /* STA */
+            0x85 | 0x95 | 0x8d | 0x9d | 0x99 | 0x81 | 0x91 => {
+                self.sta(&opcode.mode);
+            }
+
+            0x69 | 0x65 | 0x75 | 0x6d | 0x7d | 0x79 | 0x61 | 0x71 => {
+                self.adc(&opcode.mode);
+            }
 
-                0x69 | 0x65 | 0x75 | 0x6d | 0x7d | 0x79 | 0x61 | 0x71 => {
-                    self.adc(&opcode.mode);
-                } 
-                
-                0xAA => self.tax(),
-                0xe8 => self.inx(),
-                0x00 => return,
-                _ => todo!(),
+            0xe9 | 0xe5 | 0xf5 | 0xed | 0xfd | 0xf9 | 0xe1 | 0xf1 => {
+                self.sbc(&opcode.mode);
             }
 
-            if program_counter_state == self.program_counter {
-                self.program_counter += (opcode.len - 1) as u16;
+            0xAA => self.tax(),
+            0xe8 => self.inx(),
+            0xC8 => self.iny(),
+            0xCA => self.dex(),
+            0x88 => self.dey(),
+
+            0xE6 | 0xF6 | 0xEE | 0xFE => self.inc(&opcode.mode),
+            0xC6 | 0xD6 | 0xCE | 0xDE => self.dec(&opcode.mode),
+
+            0x90 => self.branch(!self.status.contains(StatusFlags::CARRY)),
+            0xB0 => self.branch(self.status.contains(StatusFlags::CARRY)),
+            0xF0 => self.branch(self.status.contains(StatusFlags::ZERO)),
+            0xD0 => self.branch(!self.status.contains(StatusFlags::ZERO)),
+            0x30 => self.branch(self.status.contains(StatusFlags::NEGATIVE)),
+            0x10 => self.branch(!self.status.contains(StatusFlags::NEGATIVE)),
+            0x50 => self.branch(!self.status.contains(StatusFlags::OVERFLOW)),
+            0x70 => self.branch(self.status.contains(StatusFlags::OVERFLOW)),
+
+            0x4C => self.jmp(&AddressingMode::Absolute),
+            0x6C => self.jmp(&AddressingMode::Indirect),
+
+            0x20 => self.jsr(),
+            0x60 => self.rts(),
+            0x48 => self.pha(),
+            0x68 => self.pla(),
+            0x08 => self.php(),
+            0x28 => self.plp(),
+
+            /* CMOS (65C02) only - NMOS falls through to the unknown-opcode path below */
+            0x80 if self.variant == Cpu6502Variant::Cmos => self.bra(),
+            0x64 | 0x74 | 0x9C | 0x9E if self.variant == Cpu6502Variant::Cmos => self.stz(&opcode.mode),
+            0xB2 if self.variant == Cpu6502Variant::Cmos => self.lda(&opcode.mode),
+            0xDA if self.variant == Cpu6502Variant::Cmos => self.phx(),
+            0x5A if self.variant == Cpu6502Variant::Cmos => self.phy(),
+            0xFA if self.variant == Cpu6502Variant::Cmos => self.plx(),
+            0x7A if self.variant == Cpu6502Variant::Cmos => self.ply(),
+            0x1A if self.variant == Cpu6502Variant::Cmos => self.inc_a(),
+            0x3A if self.variant == Cpu6502Variant::Cmos => self.dec_a(),
+            0x04 | 0x0C if self.variant == Cpu6502Variant::Cmos => self.tsb(&opcode.mode),
+            0x14 | 0x1C if self.variant == Cpu6502Variant::Cmos => self.trb(&opcode.mode),
+            0x89 if self.variant == Cpu6502Variant::Cmos => self.bit(&opcode.mode),
+
+            0x00 => {
+                // The 65C02 fixed a long-standing NMOS quirk: BRK no longer leaves
+                // the CPU in decimal mode on return.
+                if self.variant == Cpu6502Variant::Cmos {
+                    self.status.remove(StatusFlags::DECIMAL_MODE);
+                }
+                return false;
             }
+            _ => todo!(),
+        }
+
+        if program_counter_state == self.program_counter {
+            self.program_counter += (opcode.bytes - 1) as u16;
         }
+
+        true
     }
 
     fn inx(&mut self) {
-        self.register_x = self.register_x.wrapping_add(1);
-        self.update_zero_and_negative_flags();
+        increment(&mut self.register_x, &mut self.status, 1);
     }
-    
+
+    fn iny(&mut self) {
+        increment(&mut self.register_y, &mut self.status, 1);
+    }
+
+    fn dex(&mut self) {
+        increment(&mut self.register_x, &mut self.status, -1);
+    }
+
+    fn dey(&mut self) {
+        increment(&mut self.register_y, &mut self.status, -1);
+    }
+
+    fn inc(&mut self, mode: &AddressingMode) {
+        let addr = self.get_operand_address(mode);
+        let mut value = self.mem_read(addr);
+        increment(&mut value, &mut self.status, 1);
+        self.mem_write(addr, value);
+    }
+
+    fn dec(&mut self, mode: &AddressingMode) {
+        let addr = self.get_operand_address(mode);
+        let mut value = self.mem_read(addr);
+        increment(&mut value, &mut self.status, -1);
+        self.mem_write(addr, value);
+    }
+
+    /// CMOS-only: INC/DEC's accumulator forms, filling the gap left by the
+    /// NMOS 6502 (which can only INC/DEC memory).
+    fn inc_a(&mut self) {
+        let mut value = self.register_a;
+        increment(&mut value, &mut self.status, 1);
+        self.register_a = value;
+    }
+
+    fn dec_a(&mut self) {
+        let mut value = self.register_a;
+        increment(&mut value, &mut self.status, -1);
+        self.register_a = value;
+    }
+
     fn tax(&mut self) {
-        // Copies contents of register_a into register_x 
+        // Copies contents of register_a into register_x
         self.register_x = self.register_a;
-        self.update_zero_and_negative_flags();
+        self.update_zero_and_negative_flags(self.register_x);
     }
-    
-    fn lda(&mut self, mode: &AddressingMode) {  
+
+    fn lda(&mut self, mode: &AddressingMode) {
         let addr = self.get_operand_address(mode);
         let value = self.mem_read(addr);
 
         // Load parameter into register_a
         self.register_a = value;
-        self.update_zero_and_negative_flags();
+        self.update_zero_and_negative_flags(self.register_a);
     }
 
     fn sta(&mut self, mode: &AddressingMode) {
@@ -170,28 +306,192 @@ impl CPU {
         self.mem_write(addr, self.register_a);
     }
 
+    /// CMOS-only: stores zero without disturbing the accumulator.
+    fn stz(&mut self, mode: &AddressingMode) {
+        let addr = self.get_operand_address(mode);
+        self.mem_write(addr, 0);
+    }
+
+    fn branch(&mut self, condition: bool) {
+        if condition {
+            let target = self.get_operand_address(&AddressingMode::Relative);
+            self.program_counter = target;
+        }
+    }
+
+    fn jmp(&mut self, mode: &AddressingMode) {
+        self.program_counter = self.get_operand_address(mode);
+    }
+
+    /// CMOS-only unconditional branch; reuses the same relative-offset path as
+    /// the conditional branches, just without a flag to test.
+    fn bra(&mut self) {
+        self.branch(true);
+    }
+
+    fn jsr(&mut self) {
+        let target = self.mem_read_u16(self.program_counter);
+        // Return address pushed is the last byte of the JSR instruction; RTS adds 1 back.
+        self.stack_push_u16(self.program_counter.wrapping_add(1));
+        self.program_counter = target;
+    }
+
+    fn rts(&mut self) {
+        let addr = self.stack_pop_u16();
+        self.program_counter = addr.wrapping_add(1);
+    }
+
+    fn pha(&mut self) {
+        self.stack_push(self.register_a);
+    }
+
+    fn pla(&mut self) {
+        self.register_a = self.stack_pop();
+        self.update_zero_and_negative_flags(self.register_a);
+    }
+
+    fn php(&mut self) {
+        // PHP always pushes the status with BREAK and BREAK2 set, regardless of their live state
+        let flags = self.status.bits() | StatusFlags::BREAK.bits() | StatusFlags::BREAK2.bits();
+        self.stack_push(flags);
+    }
+
+    fn plp(&mut self) {
+        self.status = StatusFlags::from_bits_truncate(self.stack_pop());
+        self.status.remove(StatusFlags::BREAK);
+        self.status.insert(StatusFlags::BREAK2);
+    }
+
+    /// CMOS-only: push/pull X and Y, mirroring PHA/PLA.
+    fn phx(&mut self) {
+        self.stack_push(self.register_x);
+    }
+
+    fn phy(&mut self) {
+        self.stack_push(self.register_y);
+    }
+
+    fn plx(&mut self) {
+        self.register_x = self.stack_pop();
+        self.update_zero_and_negative_flags(self.register_x);
+    }
+
+    fn ply(&mut self) {
+        self.register_y = self.stack_pop();
+        self.update_zero_and_negative_flags(self.register_y);
+    }
+
     fn adc(&mut self, mode: &AddressingMode) {
         let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr) as u16;
+        let value = self.mem_read(addr);
+        self.add_to_register_a(value);
+    }
 
-        let result = self.register_a as u16 + value + (if self.status.contains(StatusFlags::CARRY) { 1 } else { 0 });
+    fn sbc(&mut self, mode: &AddressingMode) {
+        let addr = self.get_operand_address(mode);
+        let value = self.mem_read(addr);
+        self.subtract_from_register_a(value);
+    }
 
-        if result > 0xFF {
+    /// CMOS-only: sets Z as `BIT` would (`(A & M) == 0`), then ORs `A` into memory
+    /// without touching `A` itself.
+    fn tsb(&mut self, mode: &AddressingMode) {
+        let addr = self.get_operand_address(mode);
+        let value = self.mem_read(addr);
+        self.set_bit_test_zero_flag(value);
+        self.mem_write(addr, value | self.register_a);
+    }
+
+    /// CMOS-only: sets Z as `BIT` would, then clears the bits of memory that are
+    /// set in `A`.
+    fn trb(&mut self, mode: &AddressingMode) {
+        let addr = self.get_operand_address(mode);
+        let value = self.mem_read(addr);
+        self.set_bit_test_zero_flag(value);
+        self.mem_write(addr, value & !self.register_a);
+    }
+
+    /// CMOS-only: immediate-mode `BIT`, which (unlike the zero-page/absolute forms)
+    /// only affects the zero flag - there's no memory operand to source N/V from.
+    fn bit(&mut self, mode: &AddressingMode) {
+        let addr = self.get_operand_address(mode);
+        let value = self.mem_read(addr);
+        self.set_bit_test_zero_flag(value);
+    }
+
+    fn set_bit_test_zero_flag(&mut self, value: u8) {
+        if value & self.register_a == 0 {
+            self.status.insert(StatusFlags::ZERO);
+        } else {
+            self.status.remove(StatusFlags::ZERO);
+        }
+    }
+
+    fn add_to_register_a(&mut self, value: u8) {
+        let carry_in: u8 = if self.status.contains(StatusFlags::CARRY) { 1 } else { 0 };
+        let a = self.register_a;
+
+        // Overflow always reflects the plain binary addition, decimal mode or not.
+        let binary_sum = a as u16 + value as u16 + carry_in as u16;
+        let binary_result = binary_sum as u8;
+        let overflow = (a ^ binary_result) & (value ^ binary_result) & 0x80 != 0;
+
+        let (result, carry_out) = if self.status.contains(StatusFlags::DECIMAL_MODE) {
+            decimal_add(a, value, carry_in)
+        } else {
+            (binary_result, binary_sum > 0xFF)
+        };
+
+        if carry_out {
             self.set_carry_flag();
         } else {
             self.unset_carry_flag();
         }
 
-        // Set overflow flag if bit 8 is a different sign than the result of the addition
-        // i.e if we add 64 + 64 then bit 8 will be set which indicated a negative number in 8-bit systems
+        if overflow {
+            self.status.insert(StatusFlags::OVERFLOW);
+        } else {
+            self.status.remove(StatusFlags::OVERFLOW);
+        }
+
+        self.register_a = result;
+        self.update_zero_and_negative_flags(self.register_a);
+    }
 
-        // So, set overflow flag if the 8th bit is carried in to but not out of. OR when the MSB is not set
-        // but the carry flag is set
+    fn subtract_from_register_a(&mut self, value: u8) {
+        let carry_in: u8 = if self.status.contains(StatusFlags::CARRY) { 1 } else { 0 };
+        let a = self.register_a;
+        // A - M - (1 - C) is the same two's-complement addition circuit as A + !M + C;
+        // that identity gives correct carry/overflow in both binary and decimal mode,
+        // but (unlike ADC) the complemented byte isn't valid BCD, so the decimal digits
+        // themselves must come from a real nibble-subtraction-with-borrow, not decimal_add.
+        let complement = value ^ 0xFF;
+
+        let binary_sum = a as u16 + complement as u16 + carry_in as u16;
+        let binary_result = binary_sum as u8;
+        let overflow = (a ^ binary_result) & (complement ^ binary_result) & 0x80 != 0;
+        let carry_out = binary_sum > 0xFF;
+
+        let result = if self.status.contains(StatusFlags::DECIMAL_MODE) {
+            decimal_sub(a, value, carry_in)
+        } else {
+            binary_result
+        };
 
+        if carry_out {
+            self.set_carry_flag();
+        } else {
+            self.unset_carry_flag();
+        }
 
-        self.register_a = result as u8;
+        if overflow {
+            self.status.insert(StatusFlags::OVERFLOW);
+        } else {
+            self.status.remove(StatusFlags::OVERFLOW);
+        }
 
-        self.update_zero_and_negative_flags();
+        self.register_a = result;
+        self.update_zero_and_negative_flags(self.register_a);
     }
 
     pub fn set_carry_flag(&mut self) {
@@ -201,19 +501,15 @@ impl CPU {
     pub fn unset_carry_flag(&mut self) {
         self.status.remove(StatusFlags::CARRY);
     }
-    
-    pub fn update_zero_and_negative_flags(&mut self) {
-        if self.register_a == 0 {
-            self.status.insert(StatusFlags::ZERO);
-        } else {
-            self.status.remove(StatusFlags::ZERO);
-        }
 
-        if self.register_a & 0b1000_0000 != 0 {
-            self.status.insert(StatusFlags::NEGATIVE);
-        } else {
-            self.status.remove(StatusFlags::NEGATIVE);
-        }
+    pub fn update_zero_and_negative_flags(&mut self, value: u8) {
+        set_zero_and_negative_flags(&mut self.status, value);
+    }
+
+    /// Decodes the instruction at `addr` into a mnemonic and operand string, e.g. `LDA #$05`.
+    pub fn disassemble_at(&self, addr: u16) -> String {
+        let raw: Vec<u8> = (0..3).map(|i| self.mem_read(addr.wrapping_add(i))).collect();
+        crate::disasm::decode(&raw, addr).to_string()
     }
 
     pub fn get_operand_address(&self, mode: &AddressingMode) -> u16 {
@@ -279,19 +575,137 @@ impl CPU {
                 // The data is stored with little-endian ordering but returned as normal?
                 (hi as u16) << 8 | (lo as u16)
             },
+
+            // Relative -> Used by branch instructions: a signed 8-bit offset from the address
+            // immediately following the two-byte branch instruction
+            AddressingMode::Relative => {
+                let offset = self.mem_read(self.program_counter) as i8;
+                self.program_counter.wrapping_add(1).wrapping_add(offset as u16)
+            },
+
+            // Indirect Zero Page -> CMOS-only `(zp)` addressing: reads a zero-page pointer and
+            // returns the two bytes found there, same as Indirect_X/Indirect_Y but unindexed.
+            AddressingMode::IndirectZeroPage => {
+                let ptr = self.mem_read(self.program_counter);
+                let lo = self.mem_read(ptr as u16);
+                let hi = self.mem_read(ptr.wrapping_add(1) as u16);
+                (hi as u16) << 8 | (lo as u16)
+            },
+
+            // Indirect -> Only used by JMP. Reads a pointer, then reads the target address from
+            // that pointer. Reproduces the NMOS 6502 bug where a pointer ending in 0xFF wraps
+            // within the same page instead of crossing into the next one.
+            AddressingMode::Indirect => {
+                let ptr = self.mem_read_u16(self.program_counter);
+
+                if ptr & 0x00FF == 0x00FF {
+                    let lo = self.mem_read(ptr);
+                    let hi = self.mem_read(ptr & 0xFF00);
+                    (hi as u16) << 8 | (lo as u16)
+                } else {
+                    self.mem_read_u16(ptr)
+                }
+            },
             _ => panic!("OH NO! Addressing mode: {:?} is not supported", mode)
         }
     }
 }
 
+fn set_zero_and_negative_flags(status: &mut StatusFlags, value: u8) {
+    if value == 0 {
+        status.insert(StatusFlags::ZERO);
+    } else {
+        status.remove(StatusFlags::ZERO);
+    }
+
+    if value & 0b1000_0000 != 0 {
+        status.insert(StatusFlags::NEGATIVE);
+    } else {
+        status.remove(StatusFlags::NEGATIVE);
+    }
+}
+
+/// Binary-coded-decimal adjustment for ADC when `DECIMAL_MODE` is set.
+/// SBC does not use this; its decimal digits come from `decimal_sub`.
+fn decimal_add(a: u8, value: u8, carry_in: u8) -> (u8, bool) {
+    let mut lo = (a & 0x0F) as u16 + (value & 0x0F) as u16 + carry_in as u16;
+    if lo > 9 {
+        lo += 0x06;
+    }
+
+    let mut total = (a & 0xF0) as u16 + (value & 0xF0) as u16 + if lo > 0x0F { 0x10 } else { 0 } + (lo & 0x0F);
+    let carry_out = total > 0x99;
+    if carry_out {
+        total += 0x60;
+    }
+
+    (total as u8, carry_out)
+}
+
+/// Binary-coded-decimal adjustment for SBC when `DECIMAL_MODE` is set. Subtracts
+/// nibble-by-nibble with a borrow, correcting each digit back into 0-9 range by
+/// adding 10 (rather than `decimal_add`'s add-6-on-overflow) whenever a nibble
+/// borrows from the one above it. `carry_in` is the CPU carry flag, where 0 means
+/// a borrow is already pending, matching real 6502 SBC semantics.
+fn decimal_sub(a: u8, value: u8, carry_in: u8) -> u8 {
+    let borrow_in: i16 = if carry_in == 1 { 0 } else { 1 };
+
+    let mut lo = (a & 0x0F) as i16 - (value & 0x0F) as i16 - borrow_in;
+    let mut borrow_out = 0;
+    if lo < 0 {
+        lo += 0x0A;
+        borrow_out = 1;
+    }
+
+    let mut hi = ((a >> 4) & 0x0F) as i16 - ((value >> 4) & 0x0F) as i16 - borrow_out;
+    if hi < 0 {
+        hi += 0x0A;
+    }
+
+    ((hi as u8) << 4) | (lo as u8)
+}
+
+/// Wraps `value` by `delta` (positive for INC-style, negative for DEC-style),
+/// writes it back, and updates Z/N from the result. Shared by the register
+/// increment/decrement ops (INX/INY/DEX/DEY) and the memory read-modify-write
+/// ops (INC/DEC).
+fn increment(value: &mut u8, status: &mut StatusFlags, delta: i8) {
+    *value = value.wrapping_add(delta as u8);
+    set_zero_and_negative_flags(status, *value);
+}
+
+/// Renders the instruction at the current program counter as a Nintendulator-style
+/// trace line, e.g. `8000  A9 05     LDA #$05    A:00 X:00 Y:00 P:24 SP:FD`, suitable
+/// for diffing against known-good logs in conformance tests.
+pub fn trace<M: Bus>(cpu: &CPU<M>) -> String {
+    let pc = cpu.program_counter;
+    let raw = [cpu.mem_read(pc), cpu.mem_read(pc.wrapping_add(1)), cpu.mem_read(pc.wrapping_add(2))];
+    let decoded = crate::disasm::decode(&raw, pc);
+
+    let hex_str = decoded.bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ");
+
+    format!(
+        "{:04X}  {:<9} {:<12}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X}",
+        pc,
+        hex_str,
+        decoded.to_string(),
+        cpu.register_a,
+        cpu.register_x,
+        cpu.register_y,
+        cpu.status.bits(),
+        cpu.stack_pointer,
+    )
+}
+
 /* Tests */
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::bus::Memory;
 
     #[test]
     fn test_0xa9_lda_immediate_load_data() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.load_and_run(vec![0xA9, 0x05, 0x00]);
         assert_eq!(cpu.register_a, 0x05);
         assert!(!cpu.status.contains(StatusFlags::ZERO));
@@ -300,7 +714,7 @@ mod test {
 
     #[test]
     fn test_0xa5_lda_zero_page_load_data() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.mem_write(0x10, 0x55);
         cpu.load_and_run(vec![0xA5, 0x10, 0x00]);
 
@@ -309,7 +723,7 @@ mod test {
 
     #[test]
     fn test_0xad_lda_absolute_load_data() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.mem_write_u16(0x55DD, 0x4455);
         cpu.load_and_run(vec![0xAD, 0xDD, 0x55, 0x00]);
 
@@ -318,7 +732,7 @@ mod test {
 
     #[test]
     fn test_0x85_sta_zero_page_store_a_register() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.load_and_run(vec![0xA9, 0xFF, 0x85, 0x81, 0x00]);
 
         let value = cpu.mem_read(0x81);
@@ -328,7 +742,7 @@ mod test {
 
     #[test]
     fn test_0x95_sta_zero_page_x_store_register_a() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.load_and_run(vec![0xA9, 0x01, 0xAA, 0x95, 0x01, 0x00]);
 
         let value = cpu.mem_read(0x02);
@@ -338,49 +752,49 @@ mod test {
     
     #[test]
     fn test_0xa9_lda_zero_flag() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.load_and_run(vec![0xA9, 0x00, 0x00]);
         assert!(cpu.status.contains(StatusFlags::ZERO));
     }
 
     #[test]
     fn test_0xa9_lda_negative_flag() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.load_and_run(vec![0xA9, 0xFF, 0x00]);
         assert!(cpu.status.contains(StatusFlags::NEGATIVE));
     }
 
     #[test]
     fn test_0xaa_tax_immediate_load_data() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.load_and_run(vec![0xA9, 0x05, 0xAA, 0x00]);
         assert_eq!(cpu.register_a, cpu.register_x);
     }
 
     #[test]
     fn test_0xaa_tax_zero_flag() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.load_and_run(vec![0xA9, 0x00, 0xAA, 0x00]);
         assert!(cpu.status.contains(StatusFlags::ZERO));
     }
 
     #[test]
     fn test_0xaa_tax_negative_flag() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.load_and_run(vec![0xA9, 0xFF, 0xAA, 0x00]);
         assert!(cpu.status.contains(StatusFlags::NEGATIVE));
     }
 
     #[test]
     fn test_0xe8_inx_immediate_increment() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.load_and_run(vec![0xA9, 0x05, 0xAA, 0xE8, 0x00]);
         assert_eq!(cpu.register_x, 6);
     }
 
     #[test]
     fn test_inx_overflow() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.load_and_run(vec![0xA9, 0xFF, 0xAA, 0xE8, 0xE8, 0x00]);
 
         assert_eq!(cpu.register_x, 1)
@@ -388,9 +802,336 @@ mod test {
 
     #[test]
     fn test_5_ops_working_together() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(Memory::new());
         cpu.load_and_run(vec![0xa9, 0xc0, 0xaa, 0xe8, 0x00]);
-  
+
         assert_eq!(cpu.register_x, 0xc1)
     }
+
+    #[test]
+    fn test_jsr_rts_returns_to_caller() {
+        let mut cpu = CPU::new(Memory::new());
+        // JSR $8004; BRK -- subroutine at $8004: LDA #$42; RTS
+        cpu.load_and_run(vec![0x20, 0x04, 0x80, 0x00, 0xA9, 0x42, 0x60]);
+
+        assert_eq!(cpu.register_a, 0x42);
+        assert_eq!(cpu.stack_pointer, STACK_RESET);
+    }
+
+    #[test]
+    fn test_pha_pla_round_trips_register_a() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.load_and_run(vec![0xA9, 0x37, 0x48, 0xA9, 0x00, 0x68, 0x00]);
+
+        assert_eq!(cpu.register_a, 0x37);
+        assert_eq!(cpu.stack_pointer, STACK_RESET);
+    }
+
+    #[test]
+    fn test_php_decrements_then_plp_restores_stack_pointer() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.load_and_run(vec![0x08, 0x00]);
+        assert_eq!(cpu.stack_pointer, STACK_RESET.wrapping_sub(1));
+
+        let mut cpu = CPU::new(Memory::new());
+        cpu.load_and_run(vec![0x08, 0x28, 0x00]);
+        assert_eq!(cpu.stack_pointer, STACK_RESET);
+    }
+
+    #[test]
+    fn test_iny_increments_register_y() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.load_and_run(vec![0xC8, 0x00]);
+        assert_eq!(cpu.register_y, 1);
+    }
+
+    #[test]
+    fn test_dex_dey_wrap_on_underflow() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.load_and_run(vec![0xCA, 0x88, 0x00]);
+        assert_eq!(cpu.register_x, 0xFF);
+        assert_eq!(cpu.register_y, 0xFF);
+        assert!(cpu.status.contains(StatusFlags::NEGATIVE));
+    }
+
+    #[test]
+    fn test_inc_zero_page_increments_memory() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.mem_write(0x10, 0x41);
+        cpu.load_and_run(vec![0xE6, 0x10, 0x00]);
+        assert_eq!(cpu.mem_read(0x10), 0x42);
+    }
+
+    #[test]
+    fn test_dec_zero_page_decrements_memory() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.mem_write(0x10, 0x01);
+        cpu.load_and_run(vec![0xC6, 0x10, 0x00]);
+        assert_eq!(cpu.mem_read(0x10), 0x00);
+        assert!(cpu.status.contains(StatusFlags::ZERO));
+    }
+
+    #[test]
+    fn test_inx_sets_flags_from_register_x_not_register_a() {
+        let mut cpu = CPU::new(Memory::new());
+        // register_a is loaded negative, but INX's flags must reflect register_x (1), not A.
+        cpu.load_and_run(vec![0xA9, 0xFF, 0xE8, 0x00]);
+        assert_eq!(cpu.register_x, 1);
+        assert!(!cpu.status.contains(StatusFlags::ZERO));
+        assert!(!cpu.status.contains(StatusFlags::NEGATIVE));
+    }
+
+    #[test]
+    fn test_adc_sets_overflow_on_signed_overflow() {
+        let mut cpu = CPU::new(Memory::new());
+        // 0x50 + 0x50 = 0xA0: two positives producing a negative result -> overflow.
+        cpu.load_and_run(vec![0xA9, 0x50, 0x69, 0x50, 0x00]);
+        assert_eq!(cpu.register_a, 0xA0);
+        assert!(cpu.status.contains(StatusFlags::OVERFLOW));
+        assert!(!cpu.status.contains(StatusFlags::CARRY));
+    }
+
+    #[test]
+    fn test_adc_sets_carry_without_overflow() {
+        let mut cpu = CPU::new(Memory::new());
+        // 0xFF + 0x01 = 0x00 with carry, no signed overflow (negative + positive).
+        cpu.load_and_run(vec![0xA9, 0xFF, 0x69, 0x01, 0x00]);
+        assert_eq!(cpu.register_a, 0x00);
+        assert!(cpu.status.contains(StatusFlags::CARRY));
+        assert!(!cpu.status.contains(StatusFlags::OVERFLOW));
+    }
+
+    #[test]
+    fn test_sbc_subtracts_with_borrow() {
+        let mut cpu = CPU::new(Memory::new());
+        // No SEC opcode is implemented, so the carry is still clear from reset
+        // (0) and this first SBC borrows 1 extra - exercise that directly.
+        cpu.load_and_run(vec![0xA9, 0x05, 0xE9, 0x03, 0x00]);
+        // register_a(0x05) - 0x03 - (1 - carry(0)) = 0x01
+        assert_eq!(cpu.register_a, 0x01);
+        assert!(cpu.status.contains(StatusFlags::CARRY));
+    }
+
+    #[test]
+    fn test_adc_decimal_mode_adjusts_to_bcd() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.load(vec![0x69, 0x46, 0x00]);
+        cpu.reset();
+        cpu.register_a = 0x58;
+        cpu.status.insert(StatusFlags::DECIMAL_MODE);
+        cpu.run();
+        // 58 + 46 = 104 in decimal, represented in BCD as 0x04 with carry set.
+        assert_eq!(cpu.register_a, 0x04);
+        assert!(cpu.status.contains(StatusFlags::CARRY));
+    }
+
+    #[test]
+    fn test_sbc_decimal_mode_adjusts_to_bcd() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.load(vec![0xE9, 0x12, 0x00]);
+        cpu.reset();
+        cpu.register_a = 0x42;
+        cpu.status.insert(StatusFlags::DECIMAL_MODE);
+        cpu.status.insert(StatusFlags::CARRY); // SEC: no borrow going in
+        cpu.run();
+        // 42 - 12 = 30 in decimal, represented in BCD as 0x30 with carry (no borrow) set.
+        assert_eq!(cpu.register_a, 0x30);
+        assert!(cpu.status.contains(StatusFlags::CARRY));
+    }
+
+    #[test]
+    fn test_bne_loops_until_register_x_is_zero() {
+        let mut cpu = CPU::new(Memory::new());
+        // loop: DEX; BNE loop; BRK
+        cpu.load(vec![0xCA, 0xD0, 0xFD, 0x00]);
+        cpu.reset();
+        cpu.register_x = 3;
+        cpu.run();
+        assert_eq!(cpu.register_x, 0);
+    }
+
+    #[test]
+    fn test_beq_not_taken_falls_through() {
+        let mut cpu = CPU::new(Memory::new());
+        // LDA #$01 (non-zero, ZERO flag clear); BEQ +2 (not taken); LDA #$99; BRK
+        cpu.load_and_run(vec![0xA9, 0x01, 0xF0, 0x02, 0xA9, 0x99, 0x00]);
+        assert_eq!(cpu.register_a, 0x99);
+    }
+
+    #[test]
+    fn test_jmp_absolute() {
+        let mut cpu = CPU::new(Memory::new());
+        // JMP $8005; LDA #$01 (skipped); LDA #$42; BRK
+        cpu.load_and_run(vec![0x4C, 0x05, 0x80, 0xA9, 0x01, 0xA9, 0x42, 0x00]);
+        assert_eq!(cpu.register_a, 0x42);
+    }
+
+    #[test]
+    fn test_jmp_indirect_page_boundary_bug() {
+        let mut cpu = CPU::new(Memory::new());
+        // Pointer at $30FF: low byte read from $30FF, high byte incorrectly wraps to $3000
+        // instead of crossing into $3100, reproducing the NMOS 6502 indirect-JMP bug.
+        cpu.mem_write(0x30FF, 0x00);
+        cpu.mem_write(0x3000, 0x90);
+        cpu.mem_write(0x3100, 0x12);
+        cpu.mem_write(0x9000, 0x00); // BRK at the (buggy) target address
+
+        cpu.load_and_run(vec![0x6C, 0xFF, 0x30]);
+        assert_eq!(cpu.program_counter, 0x9001);
+    }
+
+    #[test]
+    fn test_step_executes_a_single_instruction() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.load(vec![0xA9, 0x05, 0xAA, 0x00]);
+        cpu.reset();
+
+        assert!(cpu.step()); // LDA #$05
+        assert_eq!(cpu.register_a, 0x05);
+        assert_eq!(cpu.register_x, 0);
+
+        assert!(cpu.step()); // TAX
+        assert_eq!(cpu.register_x, 0x05);
+
+        assert!(!cpu.step()); // BRK
+    }
+
+    #[test]
+    fn test_run_with_callback_observes_every_step() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.load(vec![0xA9, 0x05, 0xAA, 0x00]);
+        cpu.reset();
+
+        let mut seen = vec![];
+        cpu.run_with_callback(|cpu| {
+            seen.push(cpu.register_a);
+        });
+        assert_eq!(seen, vec![0x00, 0x05, 0x05]);
+    }
+
+    #[test]
+    fn test_trace_formats_a_nintendulator_style_line() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.load(vec![0xA9, 0x05]);
+        cpu.reset();
+
+        let line = trace(&cpu);
+        assert!(line.starts_with("8000  A9 05     LDA #$05"));
+        assert!(line.ends_with("A:00 X:00 Y:00 P:24 SP:FD"));
+    }
+
+    #[test]
+    fn test_cmos_bra_branches_unconditionally() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.variant = Cpu6502Variant::Cmos;
+        // BRA $8004; LDA #$01 (skipped); LDA #$42; BRK
+        cpu.load_and_run(vec![0x80, 0x02, 0xA9, 0x01, 0xA9, 0x42, 0x00]);
+        assert_eq!(cpu.register_a, 0x42);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_nmos_treats_cmos_only_opcodes_as_unimplemented() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.load_and_run(vec![0x80, 0x00]);
+    }
+
+    #[test]
+    fn test_cmos_stz_stores_zero_without_touching_accumulator() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.variant = Cpu6502Variant::Cmos;
+        cpu.mem_write(0x10, 0xFF);
+        cpu.load_and_run(vec![0xA9, 0x37, 0x64, 0x10, 0x00]);
+
+        assert_eq!(cpu.mem_read(0x10), 0x00);
+        assert_eq!(cpu.register_a, 0x37);
+    }
+
+    #[test]
+    fn test_cmos_lda_indirect_zero_page_wraps_pointer_within_zero_page() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.variant = Cpu6502Variant::Cmos;
+        // Pointer at zero-page $FF: low byte read from $FF, high byte must wrap to
+        // $00 instead of crossing into $0100.
+        cpu.mem_write(0x00FF, 0x00); // low byte of target address
+        cpu.mem_write(0x0000, 0x20); // high byte of target address (wrapped) -> $2000
+        cpu.mem_write(0x0100, 0x99); // decoy: where a broken wrap would read the high byte from
+        cpu.mem_write(0x2000, 0xAB); // the real data, at the correctly-wrapped target
+
+        cpu.load_and_run(vec![0xB2, 0xFF, 0x00]);
+        assert_eq!(cpu.register_a, 0xAB);
+    }
+
+    #[test]
+    fn test_cmos_phx_phy_plx_ply_round_trip() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.variant = Cpu6502Variant::Cmos;
+        // X=1 (via TAX), Y=2 (via INY,INY); push both, clobber both, then pull back in order.
+        cpu.load_and_run(vec![
+            0xA9, 0x01, 0xAA, // LDA #$01; TAX -> X=1
+            0xC8, 0xC8,       // INY; INY -> Y=2
+            0xDA, 0x5A,       // PHX; PHY
+            0xE8, 0xE8,       // INX; INX -> X=3 (clobber)
+            0xC8,             // INY -> Y=3 (clobber)
+            0x7A, 0xFA,       // PLY; PLX -> restore Y then X
+            0x00,
+        ]);
+
+        assert_eq!(cpu.register_x, 0x01);
+        assert_eq!(cpu.register_y, 0x02);
+        assert_eq!(cpu.stack_pointer, STACK_RESET);
+    }
+
+    #[test]
+    fn test_cmos_inc_a_dec_a_operate_on_accumulator() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.variant = Cpu6502Variant::Cmos;
+        cpu.load_and_run(vec![0xA9, 0x05, 0x1A, 0x3A, 0x3A, 0x00]);
+        assert_eq!(cpu.register_a, 0x04);
+    }
+
+    #[test]
+    fn test_cmos_tsb_sets_zero_flag_and_ors_memory() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.variant = Cpu6502Variant::Cmos;
+        cpu.mem_write(0x10, 0b0000_0001);
+        cpu.load_and_run(vec![0xA9, 0b0000_0010, 0x04, 0x10, 0x00]);
+
+        assert_eq!(cpu.mem_read(0x10), 0b0000_0011);
+        assert!(cpu.status.contains(StatusFlags::ZERO));
+    }
+
+    #[test]
+    fn test_cmos_trb_sets_zero_flag_and_clears_memory() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.variant = Cpu6502Variant::Cmos;
+        cpu.mem_write(0x10, 0b0000_0011);
+        cpu.load_and_run(vec![0xA9, 0b0000_0001, 0x14, 0x10, 0x00]);
+
+        assert_eq!(cpu.mem_read(0x10), 0b0000_0010);
+        assert!(!cpu.status.contains(StatusFlags::ZERO));
+    }
+
+    #[test]
+    fn test_cmos_bit_immediate_only_sets_zero_flag() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.variant = Cpu6502Variant::Cmos;
+        // A=0xF0, BIT #$0F has no overlapping bits -> Z set, N unaffected by the operand's bit 7.
+        cpu.load_and_run(vec![0xA9, 0xF0, 0x89, 0x0F, 0x00]);
+
+        assert!(cpu.status.contains(StatusFlags::ZERO));
+        assert!(cpu.status.contains(StatusFlags::NEGATIVE));
+    }
+
+    #[test]
+    fn test_cmos_brk_clears_decimal_flag() {
+        let mut cpu = CPU::new(Memory::new());
+        cpu.variant = Cpu6502Variant::Cmos;
+        cpu.load(vec![0x00]);
+        cpu.reset();
+        cpu.status.insert(StatusFlags::DECIMAL_MODE);
+        cpu.run();
+
+        assert!(!cpu.status.contains(StatusFlags::DECIMAL_MODE));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,152 @@
+use std::fmt;
+
+use crate::cpu::AddressingMode;
+use crate::opcodes::OPCODES_MAP;
+
+/// A single decoded instruction: its address, raw bytes, mnemonic, and formatted operand.
+pub struct DecodedInstruction {
+    pub address: u16,
+    pub bytes: Vec<u8>,
+    pub mnemonic: String,
+    pub operand: String,
+}
+
+impl fmt::Display for DecodedInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.operand.is_empty() {
+            write!(f, "{}", self.mnemonic)
+        } else {
+            write!(f, "{} {}", self.mnemonic, self.operand)
+        }
+    }
+}
+
+/// Decodes a single instruction from the start of `code`, using `OPCODES_MAP`
+/// for its length and addressing mode. `code` only needs to be at least as
+/// long as the instruction; a shorter slice at the end of a stream is decoded
+/// with whatever bytes are available. Unknown opcodes come back as a
+/// `.byte $xx` pseudo-instruction instead of panicking.
+pub fn decode(code: &[u8], addr: u16) -> DecodedInstruction {
+    let opcode_byte = code[0];
+
+    match OPCODES_MAP.get(&opcode_byte) {
+        Some(opcode) => {
+            let len = (opcode.bytes as usize).min(code.len());
+            let raw = code[..len].to_vec();
+            let operand = format_operand(&opcode.mode, &raw, addr);
+
+            DecodedInstruction {
+                address: addr,
+                bytes: raw,
+                mnemonic: opcode.label.clone(),
+                operand,
+            }
+        }
+        None => DecodedInstruction {
+            address: addr,
+            bytes: vec![opcode_byte],
+            mnemonic: format!(".byte ${:02x}", opcode_byte),
+            operand: String::new(),
+        },
+    }
+}
+
+/// Iterates over a byte slice, decoding one instruction per step.
+pub struct Disassembler<'a> {
+    code: &'a [u8],
+    pos: usize,
+    addr: u16,
+}
+
+impl<'a> Disassembler<'a> {
+    pub fn new(code: &'a [u8], start_addr: u16) -> Self {
+        Disassembler { code, pos: 0, addr: start_addr }
+    }
+}
+
+impl<'a> Iterator for Disassembler<'a> {
+    type Item = DecodedInstruction;
+
+    fn next(&mut self) -> Option<DecodedInstruction> {
+        if self.pos >= self.code.len() {
+            return None;
+        }
+
+        let decoded = decode(&self.code[self.pos..], self.addr);
+        self.pos += decoded.bytes.len();
+        self.addr = self.addr.wrapping_add(decoded.bytes.len() as u16);
+
+        Some(decoded)
+    }
+}
+
+fn format_operand(mode: &AddressingMode, raw: &[u8], addr: u16) -> String {
+    match mode {
+        AddressingMode::Immediate => format!("#${:02x}", operand_u8(raw)),
+        AddressingMode::ZeroPage => format!("${:02x}", operand_u8(raw)),
+        AddressingMode::ZeroPage_X => format!("${:02x},X", operand_u8(raw)),
+        AddressingMode::ZeroPage_Y => format!("${:02x},Y", operand_u8(raw)),
+        AddressingMode::Absolute => format!("${:04x}", operand_u16(raw)),
+        AddressingMode::Absolute_X => format!("${:04x},X", operand_u16(raw)),
+        AddressingMode::Absolute_Y => format!("${:04x},Y", operand_u16(raw)),
+        AddressingMode::Indirect => format!("(${:04x})", operand_u16(raw)),
+        AddressingMode::Indirect_X => format!("(${:02x},X)", operand_u8(raw)),
+        AddressingMode::Indirect_Y => format!("(${:02x}),Y", operand_u8(raw)),
+        AddressingMode::IndirectZeroPage => format!("(${:02x})", operand_u8(raw)),
+        AddressingMode::Relative => {
+            let offset = operand_u8(raw) as i8;
+            let target = addr.wrapping_add(2).wrapping_add(offset as u16);
+            format!("${:04x}", target)
+        }
+        AddressingMode::NoneAddressing => String::new(),
+    }
+}
+
+fn operand_u8(raw: &[u8]) -> u8 {
+    raw.get(1).copied().unwrap_or(0)
+}
+
+fn operand_u16(raw: &[u8]) -> u16 {
+    let lo = operand_u8(raw) as u16;
+    let hi = raw.get(2).copied().unwrap_or(0) as u16;
+    (hi << 8) | lo
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_immediate_lda() {
+        let instr = decode(&[0xA9, 0x05], 0x8000);
+        assert_eq!(instr.to_string(), "LDA #$05");
+        assert_eq!(instr.bytes, vec![0xA9, 0x05]);
+    }
+
+    #[test]
+    fn test_decode_indirect_y() {
+        let instr = decode(&[0xB1, 0x20], 0x8000);
+        assert_eq!(instr.to_string(), "LDA ($20),Y");
+    }
+
+    #[test]
+    fn test_decode_branch_shows_target_address() {
+        // BNE with offset -3 from $8002 (address after the instruction)
+        let instr = decode(&[0xD0, 0xFD], 0x8000);
+        assert_eq!(instr.to_string(), "BNE $7fff");
+    }
+
+    #[test]
+    fn test_decode_unknown_opcode_is_byte_pseudo_op() {
+        let instr = decode(&[0x02], 0x8000);
+        assert_eq!(instr.to_string(), ".byte $02");
+        assert_eq!(instr.bytes, vec![0x02]);
+    }
+
+    #[test]
+    fn test_disassembler_iterates_whole_stream() {
+        let code = [0xA9, 0x05, 0xAA, 0x00];
+        let decoded: Vec<String> = Disassembler::new(&code, 0x8000).map(|i| i.to_string()).collect();
+        assert_eq!(decoded, vec!["LDA #$05", "TAX", "BRK"]);
+    }
+}
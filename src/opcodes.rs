@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use crate::cpu::{AddressingMode, OpCode};
+
+lazy_static! {
+    pub static ref CPU_OP_CODES: Vec<OpCode> = vec![
+        OpCode { instruction: 0x00, label: "BRK".to_string(), bytes: 1, cycles: 7, mode: AddressingMode::NoneAddressing },
+
+        /* TAX / INX */
+        OpCode { instruction: 0xAA, label: "TAX".to_string(), bytes: 1, cycles: 2, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0xE8, label: "INX".to_string(), bytes: 1, cycles: 2, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0xC8, label: "INY".to_string(), bytes: 1, cycles: 2, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0xCA, label: "DEX".to_string(), bytes: 1, cycles: 2, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0x88, label: "DEY".to_string(), bytes: 1, cycles: 2, mode: AddressingMode::NoneAddressing },
+
+        /* INC */
+        OpCode { instruction: 0xE6, label: "INC".to_string(), bytes: 2, cycles: 5, mode: AddressingMode::ZeroPage },
+        OpCode { instruction: 0xF6, label: "INC".to_string(), bytes: 2, cycles: 6, mode: AddressingMode::ZeroPage_X },
+        OpCode { instruction: 0xEE, label: "INC".to_string(), bytes: 3, cycles: 6, mode: AddressingMode::Absolute },
+        OpCode { instruction: 0xFE, label: "INC".to_string(), bytes: 3, cycles: 7, mode: AddressingMode::Absolute_X },
+
+        /* DEC */
+        OpCode { instruction: 0xC6, label: "DEC".to_string(), bytes: 2, cycles: 5, mode: AddressingMode::ZeroPage },
+        OpCode { instruction: 0xD6, label: "DEC".to_string(), bytes: 2, cycles: 6, mode: AddressingMode::ZeroPage_X },
+        OpCode { instruction: 0xCE, label: "DEC".to_string(), bytes: 3, cycles: 6, mode: AddressingMode::Absolute },
+        OpCode { instruction: 0xDE, label: "DEC".to_string(), bytes: 3, cycles: 7, mode: AddressingMode::Absolute_X },
+
+        /* Branches */
+        OpCode { instruction: 0x90, label: "BCC".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Relative },
+        OpCode { instruction: 0xB0, label: "BCS".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Relative },
+        OpCode { instruction: 0xF0, label: "BEQ".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Relative },
+        OpCode { instruction: 0xD0, label: "BNE".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Relative },
+        OpCode { instruction: 0x30, label: "BMI".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Relative },
+        OpCode { instruction: 0x10, label: "BPL".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Relative },
+        OpCode { instruction: 0x50, label: "BVC".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Relative },
+        OpCode { instruction: 0x70, label: "BVS".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Relative },
+
+        /* Jumps */
+        OpCode { instruction: 0x4C, label: "JMP".to_string(), bytes: 3, cycles: 3, mode: AddressingMode::Absolute },
+        OpCode { instruction: 0x6C, label: "JMP".to_string(), bytes: 3, cycles: 5, mode: AddressingMode::Indirect },
+
+        /* Stack / subroutines */
+        OpCode { instruction: 0x20, label: "JSR".to_string(), bytes: 3, cycles: 6, mode: AddressingMode::Absolute },
+        OpCode { instruction: 0x60, label: "RTS".to_string(), bytes: 1, cycles: 6, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0x48, label: "PHA".to_string(), bytes: 1, cycles: 3, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0x68, label: "PLA".to_string(), bytes: 1, cycles: 4, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0x08, label: "PHP".to_string(), bytes: 1, cycles: 3, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0x28, label: "PLP".to_string(), bytes: 1, cycles: 4, mode: AddressingMode::NoneAddressing },
+
+        /* LDA */
+        OpCode { instruction: 0xA9, label: "LDA".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Immediate },
+        OpCode { instruction: 0xA5, label: "LDA".to_string(), bytes: 2, cycles: 3, mode: AddressingMode::ZeroPage },
+        OpCode { instruction: 0xB5, label: "LDA".to_string(), bytes: 2, cycles: 4, mode: AddressingMode::ZeroPage_X },
+        OpCode { instruction: 0xAD, label: "LDA".to_string(), bytes: 3, cycles: 4, mode: AddressingMode::Absolute },
+        OpCode { instruction: 0xBD, label: "LDA".to_string(), bytes: 3, cycles: 4, mode: AddressingMode::Absolute_X },
+        OpCode { instruction: 0xB9, label: "LDA".to_string(), bytes: 3, cycles: 4, mode: AddressingMode::Absolute_Y },
+        OpCode { instruction: 0xA1, label: "LDA".to_string(), bytes: 2, cycles: 6, mode: AddressingMode::Indirect_X },
+        OpCode { instruction: 0xB1, label: "LDA".to_string(), bytes: 2, cycles: 5, mode: AddressingMode::Indirect_Y },
+
+        /* STA */
+        OpCode { instruction: 0x85, label: "STA".to_string(), bytes: 2, cycles: 3, mode: AddressingMode::ZeroPage },
+        OpCode { instruction: 0x95, label: "STA".to_string(), bytes: 2, cycles: 4, mode: AddressingMode::ZeroPage_X },
+        OpCode { instruction: 0x8D, label: "STA".to_string(), bytes: 3, cycles: 4, mode: AddressingMode::Absolute },
+        OpCode { instruction: 0x9D, label: "STA".to_string(), bytes: 3, cycles: 5, mode: AddressingMode::Absolute_X },
+        OpCode { instruction: 0x99, label: "STA".to_string(), bytes: 3, cycles: 5, mode: AddressingMode::Absolute_Y },
+        OpCode { instruction: 0x81, label: "STA".to_string(), bytes: 2, cycles: 6, mode: AddressingMode::Indirect_X },
+        OpCode { instruction: 0x91, label: "STA".to_string(), bytes: 2, cycles: 6, mode: AddressingMode::Indirect_Y },
+
+        /* ADC */
+        OpCode { instruction: 0x69, label: "ADC".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Immediate },
+        OpCode { instruction: 0x65, label: "ADC".to_string(), bytes: 2, cycles: 3, mode: AddressingMode::ZeroPage },
+        OpCode { instruction: 0x75, label: "ADC".to_string(), bytes: 2, cycles: 4, mode: AddressingMode::ZeroPage_X },
+        OpCode { instruction: 0x6D, label: "ADC".to_string(), bytes: 3, cycles: 4, mode: AddressingMode::Absolute },
+        OpCode { instruction: 0x7D, label: "ADC".to_string(), bytes: 3, cycles: 4, mode: AddressingMode::Absolute_X },
+        OpCode { instruction: 0x79, label: "ADC".to_string(), bytes: 3, cycles: 4, mode: AddressingMode::Absolute_Y },
+        OpCode { instruction: 0x61, label: "ADC".to_string(), bytes: 2, cycles: 6, mode: AddressingMode::Indirect_X },
+        OpCode { instruction: 0x71, label: "ADC".to_string(), bytes: 2, cycles: 5, mode: AddressingMode::Indirect_Y },
+
+        /* SBC */
+        OpCode { instruction: 0xE9, label: "SBC".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Immediate },
+        OpCode { instruction: 0xE5, label: "SBC".to_string(), bytes: 2, cycles: 3, mode: AddressingMode::ZeroPage },
+        OpCode { instruction: 0xF5, label: "SBC".to_string(), bytes: 2, cycles: 4, mode: AddressingMode::ZeroPage_X },
+        OpCode { instruction: 0xED, label: "SBC".to_string(), bytes: 3, cycles: 4, mode: AddressingMode::Absolute },
+        OpCode { instruction: 0xFD, label: "SBC".to_string(), bytes: 3, cycles: 4, mode: AddressingMode::Absolute_X },
+        OpCode { instruction: 0xF9, label: "SBC".to_string(), bytes: 3, cycles: 4, mode: AddressingMode::Absolute_Y },
+        OpCode { instruction: 0xE1, label: "SBC".to_string(), bytes: 2, cycles: 6, mode: AddressingMode::Indirect_X },
+        OpCode { instruction: 0xF1, label: "SBC".to_string(), bytes: 2, cycles: 5, mode: AddressingMode::Indirect_Y },
+
+        /* CMOS (65C02) only */
+        OpCode { instruction: 0x80, label: "BRA".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Relative },
+        OpCode { instruction: 0x64, label: "STZ".to_string(), bytes: 2, cycles: 3, mode: AddressingMode::ZeroPage },
+        OpCode { instruction: 0x74, label: "STZ".to_string(), bytes: 2, cycles: 4, mode: AddressingMode::ZeroPage_X },
+        OpCode { instruction: 0x9C, label: "STZ".to_string(), bytes: 3, cycles: 4, mode: AddressingMode::Absolute },
+        OpCode { instruction: 0x9E, label: "STZ".to_string(), bytes: 3, cycles: 5, mode: AddressingMode::Absolute_X },
+        OpCode { instruction: 0xB2, label: "LDA".to_string(), bytes: 2, cycles: 5, mode: AddressingMode::IndirectZeroPage },
+        OpCode { instruction: 0xDA, label: "PHX".to_string(), bytes: 1, cycles: 3, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0x5A, label: "PHY".to_string(), bytes: 1, cycles: 3, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0xFA, label: "PLX".to_string(), bytes: 1, cycles: 4, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0x7A, label: "PLY".to_string(), bytes: 1, cycles: 4, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0x1A, label: "INC".to_string(), bytes: 1, cycles: 2, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0x3A, label: "DEC".to_string(), bytes: 1, cycles: 2, mode: AddressingMode::NoneAddressing },
+        OpCode { instruction: 0x04, label: "TSB".to_string(), bytes: 2, cycles: 5, mode: AddressingMode::ZeroPage },
+        OpCode { instruction: 0x0C, label: "TSB".to_string(), bytes: 3, cycles: 6, mode: AddressingMode::Absolute },
+        OpCode { instruction: 0x14, label: "TRB".to_string(), bytes: 2, cycles: 5, mode: AddressingMode::ZeroPage },
+        OpCode { instruction: 0x1C, label: "TRB".to_string(), bytes: 3, cycles: 6, mode: AddressingMode::Absolute },
+        OpCode { instruction: 0x89, label: "BIT".to_string(), bytes: 2, cycles: 2, mode: AddressingMode::Immediate },
+    ];
+
+    pub static ref OPCODES_MAP: HashMap<u8, &'static OpCode> = {
+        let mut map = HashMap::new();
+        for op in &*CPU_OP_CODES {
+            map.insert(op.instruction, op);
+        }
+        map
+    };
+}